@@ -0,0 +1,36 @@
+//! Shared bookkeeping for assembling the combined answer to a JSON-RPC batch.
+//!
+//! Both the client side (awaiting every per-entry result of a [`Client::batch`](crate::client::Client::batch))
+//! and a transport's [`RawServer`](crate::raw::RawServer) implementation (splitting an incoming
+//! batch into individual requests and flushing the combined response only once every sub-request
+//! has been answered) need the same piece of bookkeeping: track when every slot of a batch has
+//! been filled in, regardless of the order answers arrive in.
+
+use std::sync::Mutex;
+
+/// Tracks the outstanding answers of a single batch, indexed by each sub-call's position among
+/// the batch's entries.
+pub struct BatchState<T> {
+    slots: Mutex<Vec<Option<T>>>,
+}
+
+impl<T> BatchState<T> {
+    /// Creates a new state tracking `len` outstanding answers.
+    pub fn new(len: usize) -> Self {
+        BatchState { slots: Mutex::new((0..len).map(|_| None).collect()) }
+    }
+
+    /// Records the answer for sub-call `index`.
+    ///
+    /// Returns `Some(values)`, in the original call order, once every slot has been filled;
+    /// `None` if other sub-calls of the batch are still pending.
+    pub fn fill(&self, index: usize, value: T) -> Option<Vec<T>> {
+        let mut slots = self.slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        slots[index] = Some(value);
+        if slots.iter().all(Option::is_some) {
+            Some(slots.drain(..).map(|slot| slot.expect("just checked all slots are filled")).collect())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! High-level client built on top of a [`RawClient`].
+
+use crate::batch::BatchState;
+use crate::common::{self, Id, JsonValue, Params};
+use crate::raw::{RawClient, RawClientEvent};
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, BoxFuture};
+use futures::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Wraps around a [`RawClient`] and analyses/dispatches the responses and notifications it
+/// produces, exposing a simple `async fn request(...)`/`async fn subscribe(...)` API regardless
+/// of the underlying transport.
+///
+/// A background task, spawned when the `Client` is created, owns the [`RawClient`] and is the
+/// only thing that ever calls [`RawClient::next_event`]. This is what lets a [`Subscription`]
+/// keep receiving notifications for as long as the `Client` is alive, rather than only while some
+/// other call happens to be pumping the transport.
+pub struct Client<R: RawClient> {
+    to_driver: mpsc::UnboundedSender<ToDriver<R::Error>>,
+    /// Shared with the background task so that a request's id is known before it's sent, which
+    /// lets [`Client::request_with_timeout`] cancel a specific in-flight request by id.
+    next_id: Arc<AtomicU64>,
+    /// Timeout applied by [`Client::request`] when no timeout is given explicitly through
+    /// [`Client::request_with_timeout`].
+    default_timeout: Option<Duration>,
+}
+
+/// Error that can happen while performing a request.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError<E> {
+    /// Error in the underlying transport.
+    #[error("transport error: {0}")]
+    Transport(#[source] E),
+    /// The server answered with a JSON-RPC error.
+    #[error("server returned an error: {0}")]
+    Request(common::Error),
+    /// The response couldn't be decoded into the requested type.
+    #[error("failed to decode response: {0}")]
+    Deserialize(#[source] serde_json::Error),
+    /// The deadline passed before the server answered. The request has been abandoned: a late
+    /// answer, if it ever arrives, is silently discarded.
+    #[error("request timed out")]
+    Timeout,
+    /// The background task driving the underlying transport has shut down, typically because the
+    /// transport returned an error from [`RawClient::next_event`].
+    #[error("the client's background task has shut down")]
+    Disconnected,
+}
+
+/// An active subscription created through [`Client::subscribe`].
+pub struct Subscription {
+    id: JsonValue,
+    receiver: mpsc::UnboundedReceiver<JsonValue>,
+}
+
+impl Subscription {
+    /// Waits for the next notification pushed by the server for this subscription.
+    ///
+    /// Returns `None` once the underlying client has been dropped.
+    pub async fn next(&mut self) -> Option<JsonValue> {
+        self.receiver.next().await
+    }
+
+    /// Identifier the server assigned to this subscription.
+    pub fn id(&self) -> &JsonValue {
+        &self.id
+    }
+}
+
+impl<R> Client<R>
+where
+    R: RawClient + 'static,
+{
+    /// Creates a new `Client` on top of a raw transport, with no default request timeout.
+    ///
+    /// This spawns a background task that drives `raw` for as long as the returned `Client` is
+    /// alive; a [`Subscription`] on its own doesn't keep that task running.
+    pub fn new(raw: R) -> Self {
+        let (to_driver, from_client) = mpsc::unbounded();
+        async_std::task::spawn(drive(raw, from_client));
+        Client { to_driver, next_id: Arc::new(AtomicU64::new(0)), default_timeout: None }
+    }
+
+    /// Creates a new `Client` that applies `timeout` to every [`Client::request`] call, unless
+    /// overridden for a single call through [`Client::request_with_timeout`].
+    pub fn with_default_timeout(raw: R, timeout: Duration) -> Self {
+        let mut client = Self::new(raw);
+        client.default_timeout = Some(timeout);
+        client
+    }
+
+    /// Performs a request and waits for the answer, applying this client's default timeout if one
+    /// was set at construction.
+    pub async fn request<Ret>(&mut self, method: impl Into<String>, params: impl Into<Params>) -> Result<Ret, ClientError<R::Error>>
+    where
+        Ret: serde::de::DeserializeOwned,
+    {
+        match self.default_timeout {
+            Some(timeout) => self.request_with_timeout(method, params, timeout).await,
+            None => {
+                let result = self.call(method.into(), params.into()).await?;
+                serde_json::from_value(result).map_err(ClientError::Deserialize)
+            }
+        }
+    }
+
+    /// Performs a request and waits at most `timeout` for the answer, regardless of this client's
+    /// default timeout.
+    ///
+    /// The deadline covers the whole exchange, not just waiting for an already-in-flight request:
+    /// `start_call` only talks to the background task over a cheap, non-blocking channel send, so
+    /// whatever the transport actually does to send the request out (e.g. an HTTP round-trip) runs
+    /// after this future is raced against the timer, not before it. If the deadline elapses first,
+    /// [`ClientError::Timeout`] is returned and the request is abandoned: a [`ToDriver::Cancel`] is
+    /// sent so the driver drops its `pending` entry for it, rather than leaving it there to be
+    /// reclaimed only if (and whenever) a late answer happens to arrive.
+    pub async fn request_with_timeout<Ret>(
+        &mut self,
+        method: impl Into<String>,
+        params: impl Into<Params>,
+        timeout: Duration,
+    ) -> Result<Ret, ClientError<R::Error>>
+    where
+        Ret: serde::de::DeserializeOwned,
+    {
+        let (id, receiver) = self.start_call(method.into(), params.into())?;
+        let result = match future::select(receiver, futures_timer::Delay::new(timeout)).await {
+            future::Either::Left((received, _)) => received.map_err(|_| ClientError::Disconnected)??,
+            future::Either::Right((_, _)) => {
+                let _ = self.to_driver.unbounded_send(ToDriver::Cancel { id });
+                return Err(ClientError::Timeout);
+            }
+        };
+
+        serde_json::from_value(result).map_err(ClientError::Deserialize)
+    }
+
+    /// Subscribes to a pub-sub notification feed.
+    ///
+    /// `subscribe_method` is expected to return an opaque subscription id; the server is then
+    /// expected to push notifications carrying a `"subscription"` field set to that id, which are
+    /// routed to the returned [`Subscription`] by the background task for as long as the `Client`
+    /// lives, regardless of whether anything else is awaiting a request in the meantime.
+    pub async fn subscribe(
+        &mut self,
+        subscribe_method: impl Into<String>,
+        params: impl Into<Params>,
+    ) -> Result<Subscription, ClientError<R::Error>> {
+        let sub_id: JsonValue = self.request(subscribe_method, params).await?;
+        let (sender, receiver) = mpsc::unbounded();
+        self.to_driver
+            .unbounded_send(ToDriver::Subscribe { sub_id: sub_id.clone(), sender })
+            .map_err(|_| ClientError::Disconnected)?;
+        Ok(Subscription { id: sub_id, receiver })
+    }
+
+    /// Starts building a batch of requests to be sent as a single JSON-RPC batch.
+    pub fn batch(&mut self) -> BatchBuilder<'_, R> {
+        BatchBuilder { client: self, calls: Vec::new() }
+    }
+
+    /// Sends `method`/`params` to the background task and waits for its answer.
+    async fn call(&mut self, method: String, params: Params) -> Result<JsonValue, ClientError<R::Error>> {
+        let (_, receiver) = self.start_call(method, params)?;
+        receiver.await.map_err(|_| ClientError::Disconnected)?
+    }
+
+    /// Allocates an id and hands `method`/`params` to the background task, returning that id
+    /// alongside the oneshot that will carry its answer without waiting for it.
+    ///
+    /// The id is allocated here, rather than by the driver once it gets around to handling the
+    /// command, so that a caller racing the answer against a deadline (like
+    /// [`Client::request_with_timeout`]) can still refer to the request by id and cancel it if the
+    /// deadline wins.
+    fn start_call(
+        &mut self,
+        method: String,
+        params: Params,
+    ) -> Result<(u64, oneshot::Receiver<Result<JsonValue, ClientError<R::Error>>>), ClientError<R::Error>> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (answer, receiver) = oneshot::channel();
+        self.to_driver.unbounded_send(ToDriver::Request { id, method, params, answer }).map_err(|_| ClientError::Disconnected)?;
+        Ok((id, receiver))
+    }
+}
+
+/// Accumulates `(method, params)` entries to be sent as a single JSON-RPC batch through
+/// [`Client::batch`].
+pub struct BatchBuilder<'a, R: RawClient> {
+    client: &'a mut Client<R>,
+    calls: Vec<(String, Params)>,
+}
+
+impl<'a, R> BatchBuilder<'a, R>
+where
+    R: RawClient + 'static,
+{
+    /// Adds an entry to the batch.
+    pub fn push(mut self, method: impl Into<String>, params: impl Into<Params>) -> Self {
+        self.calls.push((method.into(), params.into()));
+        self
+    }
+
+    /// Sends the batch and waits for every per-entry result, correlated by id and returned in the
+    /// same order the entries were pushed in.
+    ///
+    /// An empty batch is sent as no request at all and resolves to an empty `Vec`, per the spec's
+    /// rule that a batch of nothing (or of only notifications) gets no response.
+    pub async fn send(self) -> Result<Vec<Result<JsonValue, common::Error>>, ClientError<R::Error>> {
+        let BatchBuilder { client, calls } = self;
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let calls = calls
+            .into_iter()
+            .map(|(method, params)| (client.next_id.fetch_add(1, Ordering::Relaxed), method, params))
+            .collect();
+        let (answer, receiver) = oneshot::channel();
+        client.to_driver.unbounded_send(ToDriver::Batch { calls, answer }).map_err(|_| ClientError::Disconnected)?;
+        receiver.await.map_err(|_| ClientError::Disconnected)?
+    }
+}
+
+/// Message sent from a [`Client`] (or [`BatchBuilder`]) to the background task spawned by
+/// [`Client::new`].
+enum ToDriver<E> {
+    Request { id: u64, method: String, params: Params, answer: oneshot::Sender<Result<JsonValue, ClientError<E>>> },
+    Batch { calls: Vec<(u64, String, Params)>, answer: oneshot::Sender<Result<Vec<Result<JsonValue, common::Error>>, ClientError<E>>> },
+    Subscribe { sub_id: JsonValue, sender: mpsc::UnboundedSender<JsonValue> },
+    /// Sent by [`Client::request_with_timeout`] when its deadline elapses first, so the driver
+    /// evicts `id`'s `pending` entry instead of leaving it there to be reclaimed only if (and
+    /// whenever) a late answer happens to arrive.
+    Cancel { id: u64 },
+}
+
+/// What a given request id's answer must be routed to once it comes back through
+/// [`RawClient::next_event`].
+enum PendingEntry<E> {
+    /// Answer directly the request that's waiting for it.
+    Single(oneshot::Sender<Result<JsonValue, ClientError<E>>>),
+    /// Fill slot `index` of a batch; the combined answer is sent once every slot is filled.
+    Batch { shared: Arc<SharedBatch<E>>, index: usize },
+}
+
+struct SharedBatch<E> {
+    state: BatchState<Result<JsonValue, common::Error>>,
+    /// Taken by whichever response completes the last slot.
+    answer: Mutex<Option<oneshot::Sender<Result<Vec<Result<JsonValue, common::Error>>, ClientError<E>>>>>,
+}
+
+/// Polls `raw` for its next event only while `active`, i.e. while there's at least one request or
+/// subscription that could produce one; otherwise waits forever.
+///
+/// Some transports (plain HTTP, which has nothing to wait on between requests) report
+/// [`RawClient::next_event`] as erroring the instant nothing is buffered rather than parking until
+/// something arrives. Polling it unconditionally would treat that as a fatal transport error and
+/// tear the driver down before it ever sent a single request.
+fn next_event<R: RawClient>(raw: &mut R, active: bool) -> future::Either<BoxFuture<'_, Result<RawClientEvent, R::Error>>, future::Pending<Result<RawClientEvent, R::Error>>> {
+    if active {
+        future::Either::Left(raw.next_event())
+    } else {
+        future::Either::Right(future::pending())
+    }
+}
+
+/// Runs for as long as at least one [`Client`] handle (or the `mpsc` sender it holds) is alive,
+/// driving `raw` and dispatching its events to whichever request, batch or subscription they
+/// belong to.
+async fn drive<R: RawClient>(mut raw: R, commands: mpsc::UnboundedReceiver<ToDriver<R::Error>>) {
+    let mut commands = commands.fuse();
+    let mut pending: HashMap<u64, PendingEntry<R::Error>> = HashMap::new();
+    let mut subscriptions: HashMap<JsonValue, mpsc::UnboundedSender<JsonValue>> = HashMap::new();
+
+    loop {
+        let transport_active = !pending.is_empty() || !subscriptions.is_empty();
+        futures::select! {
+            command = commands.next() => {
+                let command = match command {
+                    Some(command) => command,
+                    None => return, // every `Client` handle has been dropped
+                };
+                match command {
+                    ToDriver::Request { id, method, params, answer } => {
+                        let call = common::MethodCall { jsonrpc: common::Version::V2, method, params, id: Id::Num(id) };
+                        if let Err(err) = raw.start_request(call).await {
+                            let _ = answer.send(Err(ClientError::Transport(err)));
+                            continue;
+                        }
+                        pending.insert(id, PendingEntry::Single(answer));
+                    }
+                    ToDriver::Batch { calls, answer } => {
+                        let shared = Arc::new(SharedBatch { state: BatchState::new(calls.len()), answer: Mutex::new(Some(answer)) });
+                        let mut ids = Vec::with_capacity(calls.len());
+                        let method_calls = calls
+                            .into_iter()
+                            .enumerate()
+                            .map(|(index, (id, method, params))| {
+                                ids.push(id);
+                                pending.insert(id, PendingEntry::Batch { shared: shared.clone(), index });
+                                common::MethodCall { jsonrpc: common::Version::V2, method, params, id: Id::Num(id) }
+                            })
+                            .collect();
+
+                        if let Err(err) = raw.start_batch(method_calls).await {
+                            for id in ids {
+                                pending.remove(&id);
+                            }
+                            if let Some(answer) = shared.answer.lock().unwrap_or_else(|p| p.into_inner()).take() {
+                                let _ = answer.send(Err(ClientError::Transport(err)));
+                            }
+                        }
+                    }
+                    ToDriver::Subscribe { sub_id, sender } => {
+                        subscriptions.insert(sub_id, sender);
+                    }
+                    ToDriver::Cancel { id } => {
+                        pending.remove(&id);
+                    }
+                }
+            }
+            event = next_event(&mut raw, transport_active).fuse() => {
+                match event {
+                    Ok(RawClientEvent::Response { id: Id::Num(id), result }) => {
+                        match pending.remove(&id) {
+                            Some(PendingEntry::Single(answer)) => {
+                                let _ = answer.send(result.map_err(ClientError::Request));
+                            }
+                            Some(PendingEntry::Batch { shared, index }) => {
+                                if let Some(values) = shared.state.fill(index, result) {
+                                    if let Some(answer) = shared.answer.lock().unwrap_or_else(|p| p.into_inner()).take() {
+                                        let _ = answer.send(Ok(values));
+                                    }
+                                }
+                            }
+                            None => {}
+                        }
+                    }
+                    Ok(RawClientEvent::Response { .. }) => {}
+                    Ok(RawClientEvent::SubscriptionNotif(value)) => {
+                        if let Some(sub_id) = value.get("subscription") {
+                            if let Some(sender) = subscriptions.get(sub_id) {
+                                let result = value.get("result").cloned().unwrap_or(JsonValue::Null);
+                                let _ = sender.unbounded_send(result);
+                            }
+                        }
+                    }
+                    // The transport is gone for good; every pending oneshot is dropped along with
+                    // `pending` when this task returns, which resolves them all to `Disconnected`.
+                    Err(_) => return,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::local::local_raw;
+    use crate::server::{Server, ServerEvent};
+
+    /// Spawns a server that answers every request with its method name as a JSON string, except
+    /// for `"never_answered"`, which it receives but never responds to.
+    fn spawn_echo_server() -> Client<crate::raw::local::LocalRawClient> {
+        let (raw_client, raw_server) = local_raw();
+        async_std::task::spawn(async move {
+            let mut server = Server::new(raw_server);
+            loop {
+                match server.next_event().await {
+                    Ok(ServerEvent::Request(rq)) => {
+                        let method = rq.method().to_owned();
+                        if method != "never_answered" {
+                            let _ = rq.respond(Ok(JsonValue::from(method))).await;
+                        }
+                    }
+                    Ok(ServerEvent::Notification(_)) | Ok(ServerEvent::SubscriptionsClosed(_)) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+        Client::new(raw_client)
+    }
+
+    #[async_std::test]
+    async fn timeout_abandons_request() {
+        let mut client = spawn_echo_server();
+        let result = client
+            .request_with_timeout::<JsonValue>("never_answered", Params::None, Duration::from_millis(50))
+            .await;
+        assert!(matches!(result, Err(ClientError::Timeout)));
+    }
+
+    #[async_std::test]
+    async fn batch_round_trip() {
+        let mut client = spawn_echo_server();
+        let results = client.batch().push("a", Params::None).push("b", Params::None).send().await.unwrap();
+        assert_eq!(results, vec![Ok(JsonValue::from("a")), Ok(JsonValue::from("b"))]);
+    }
+
+    #[async_std::test]
+    async fn notification_is_routed_to_server() {
+        let (mut raw_client, raw_server) = local_raw();
+        let mut server = Server::new(raw_server);
+
+        raw_client
+            .send_notification(common::Notification {
+                jsonrpc: common::Version::V2,
+                method: "heartbeat".to_owned(),
+                params: Params::None,
+            })
+            .await
+            .unwrap();
+
+        match server.next_event().await.unwrap() {
+            ServerEvent::Notification(notif) => assert_eq!(notif.method, "heartbeat"),
+            _ => panic!("expected a notification"),
+        }
+    }
+}
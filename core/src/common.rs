@@ -0,0 +1,248 @@
+//! JSON-RPC 2.0 wire types shared by every transport and by the client/server wrappers.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Re-export of the JSON value type used throughout this crate.
+pub type JsonValue = serde_json::Value;
+
+/// Parameters of a method call or notification.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Params {
+    /// No parameters were provided.
+    None,
+    /// Parameters provided as a JSON array (positional parameters).
+    Array(Vec<JsonValue>),
+    /// Parameters provided as a JSON object (named parameters).
+    Map(serde_json::Map<String, JsonValue>),
+}
+
+impl Params {
+    fn is_none(&self) -> bool {
+        matches!(self, Params::None)
+    }
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params::None
+    }
+}
+
+impl From<()> for Params {
+    fn from(_: ()) -> Params {
+        Params::None
+    }
+}
+
+impl Serialize for Params {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Params::None => serializer.serialize_none(),
+            Params::Array(array) => array.serialize(serializer),
+            Params::Map(map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Params {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match JsonValue::deserialize(deserializer)? {
+            JsonValue::Null => Ok(Params::None),
+            JsonValue::Array(array) => Ok(Params::Array(array)),
+            JsonValue::Object(map) => Ok(Params::Map(map)),
+            _ => Err(serde::de::Error::custom("params must be an array, an object, or null")),
+        }
+    }
+}
+
+/// Identifier of a request, echoed back by the server in its response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum Id {
+    /// Numeric id, the kind generated by [`crate::client::Client`].
+    Num(u64),
+    /// String id.
+    Str(String),
+    /// Null id, only ever sent by clients that don't care about the response.
+    Null,
+}
+
+/// The `"jsonrpc"` version field. Only version 2 is supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    /// JSON-RPC version 2.
+    V2,
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("2.0")
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match String::deserialize(deserializer)?.as_str() {
+            "2.0" => Ok(Version::V2),
+            other => Err(serde::de::Error::custom(format!("unsupported jsonrpc version: {}", other))),
+        }
+    }
+}
+
+/// A single method call, expecting an answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodCall {
+    /// Protocol version.
+    pub jsonrpc: Version,
+    /// Name of the method to call.
+    pub method: String,
+    /// Parameters passed to the method.
+    #[serde(default, skip_serializing_if = "Params::is_none")]
+    pub params: Params,
+    /// Identifier of this call.
+    pub id: Id,
+}
+
+/// A notification, which unlike [`MethodCall`] doesn't expect any answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Protocol version.
+    pub jsonrpc: Version,
+    /// Name of the method being notified.
+    pub method: String,
+    /// Parameters passed alongside the notification.
+    #[serde(default, skip_serializing_if = "Params::is_none")]
+    pub params: Params,
+}
+
+/// Something sent by a client to a server: either a single call/notification, or a batch of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Request {
+    /// A single method call or notification.
+    Single(Call),
+    /// A batch of method calls and/or notifications, sent as a single JSON array.
+    Batch(Vec<Call>),
+}
+
+/// A single entry of a [`Request`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Call {
+    /// A method call expecting an answer.
+    MethodCall(MethodCall),
+    /// A notification, fire-and-forget.
+    Notification(Notification),
+}
+
+/// The outcome of a single [`MethodCall`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Output {
+    /// The call succeeded.
+    Success {
+        /// Protocol version.
+        jsonrpc: Version,
+        /// Value returned by the method.
+        result: JsonValue,
+        /// Identifier of the call this is an answer to.
+        id: Id,
+    },
+    /// The call failed.
+    Failure {
+        /// Protocol version.
+        jsonrpc: Version,
+        /// Reason for the failure.
+        error: Error,
+        /// Identifier of the call this is an answer to.
+        id: Id,
+    },
+}
+
+impl Output {
+    /// Identifier of the call this output answers.
+    pub fn id(&self) -> &Id {
+        match self {
+            Output::Success { id, .. } => id,
+            Output::Failure { id, .. } => id,
+        }
+    }
+}
+
+/// Something sent by a server to a client: either a single output, or a batch of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Response {
+    /// Answer to a single [`MethodCall`].
+    Single(Output),
+    /// Answers to a batch of calls, in the same order the calls were sent in.
+    Batch(Vec<Output>),
+}
+
+/// A JSON-RPC 2.0 error object, as carried by [`Output::Failure`] and returned by
+/// [`crate::server::params::Params::get`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Error {
+    /// Numeric code identifying the error.
+    pub code: i64,
+    /// Short, human-readable summary of the error.
+    pub message: String,
+    /// Optional extra information about the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<JsonValue>,
+}
+
+impl Error {
+    /// Builds the reserved `-32700 Parse error` error.
+    pub fn parse_error() -> Error {
+        Error::custom(-32700, "Parse error")
+    }
+
+    /// Builds the reserved `-32601 Method not found` error.
+    pub fn method_not_found() -> Error {
+        Error::custom(-32601, "Method not found")
+    }
+
+    /// Builds the reserved `-32602 Invalid params` error.
+    pub fn invalid_params() -> Error {
+        Error::custom(-32602, "Invalid params")
+    }
+
+    /// Builds the reserved `-32603 Internal error` error.
+    pub fn internal_error() -> Error {
+        Error::custom(-32603, "Internal error")
+    }
+
+    /// Builds a free-form error with no extra `data`.
+    pub fn custom(code: i64, message: impl Into<String>) -> Error {
+        Error { code, message: message.into(), data: None }
+    }
+
+    /// Attaches extra `data` to this error.
+    pub fn with_data(mut self, data: JsonValue) -> Error {
+        self.data = Some(data);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for Error {}
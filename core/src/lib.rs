@@ -0,0 +1,15 @@
+//! Transport-agnostic JSON-RPC primitives shared by every `jsonrpsee-*` transport crate and
+//! re-exported by the top-level `jsonrpsee` crate as `jsonrpsee::core`.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod batch;
+pub mod client;
+pub mod common;
+pub mod raw;
+pub mod server;
+
+pub use client::Client;
+pub use raw::{local, local::local_raw, RawClient, RawServer};
+pub use server::{Server, ServerEvent};
@@ -0,0 +1,117 @@
+//! In-memory transport connecting a [`LocalRawClient`] to a [`LocalRawServer`] without going
+//! through any actual I/O. Mostly useful for tests and examples.
+
+use crate::common;
+use crate::raw::{RawClient, RawClientEvent, RawServer, RawServerEvent};
+use futures::{channel::mpsc, future::BoxFuture, prelude::*};
+
+/// Message sent from the client side to the server side of a [`local_raw`] pair.
+#[derive(Debug)]
+enum ToServer {
+    Notification(common::Notification),
+    Request(u64, common::MethodCall),
+}
+
+/// Message sent from the server side to the client side of a [`local_raw`] pair.
+#[derive(Debug)]
+enum ToClient {
+    Response(u64, common::Output),
+    Notification(common::Notification),
+}
+
+/// Error that can be produced by [`LocalRawClient`] and [`LocalRawServer`]: the other end was
+/// dropped.
+#[derive(Debug, thiserror::Error)]
+#[error("the other end of the local transport has been dropped")]
+pub struct LocalError;
+
+/// Client side of an in-memory, channel-based transport.
+pub struct LocalRawClient {
+    to_server: mpsc::UnboundedSender<ToServer>,
+    from_server: mpsc::UnboundedReceiver<ToClient>,
+}
+
+/// Server side of an in-memory, channel-based transport.
+pub struct LocalRawServer {
+    to_client: mpsc::UnboundedSender<ToClient>,
+    from_client: mpsc::UnboundedReceiver<ToServer>,
+}
+
+/// Builds a [`LocalRawClient`] and a [`LocalRawServer`] wired up to each other.
+pub fn local_raw() -> (LocalRawClient, LocalRawServer) {
+    let (to_server, from_client) = mpsc::unbounded();
+    let (to_client, from_server) = mpsc::unbounded();
+    (LocalRawClient { to_server, from_server }, LocalRawServer { to_client, from_client })
+}
+
+impl RawClient for LocalRawClient {
+    type Error = LocalError;
+
+    fn send_notification(&mut self, notification: common::Notification) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            self.to_server.send(ToServer::Notification(notification)).await.map_err(|_| LocalError)
+        })
+    }
+
+    fn start_request(&mut self, request: common::MethodCall) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let id = match &request.id {
+                common::Id::Num(id) => *id,
+                _ => return Err(LocalError),
+            };
+            self.to_server.send(ToServer::Request(id, request)).await.map_err(|_| LocalError)
+        })
+    }
+
+    fn next_event(&mut self) -> BoxFuture<'_, Result<RawClientEvent, Self::Error>> {
+        Box::pin(async move {
+            match self.from_server.next().await.ok_or(LocalError)? {
+                ToClient::Response(id, common::Output::Success { result, .. }) => {
+                    Ok(RawClientEvent::Response { id: common::Id::Num(id), result: Ok(result) })
+                }
+                ToClient::Response(id, common::Output::Failure { error, .. }) => {
+                    Ok(RawClientEvent::Response { id: common::Id::Num(id), result: Err(error) })
+                }
+                ToClient::Notification(notif) => {
+                    let value = serde_json::to_value(&notif.params).unwrap_or(common::JsonValue::Null);
+                    Ok(RawClientEvent::SubscriptionNotif(value))
+                }
+            }
+        })
+    }
+}
+
+impl RawServer for LocalRawServer {
+    type RequestId = u64;
+    type Error = LocalError;
+
+    fn next_request(&mut self) -> BoxFuture<'_, Result<RawServerEvent<u64>, Self::Error>> {
+        Box::pin(async move {
+            match self.from_client.next().await.ok_or(LocalError)? {
+                ToServer::Notification(notif) => Ok(RawServerEvent::Notification(notif)),
+                ToServer::Request(id, request) => {
+                    Ok(RawServerEvent::Request { id, request, path: "/".to_owned() })
+                }
+            }
+        })
+    }
+
+    fn finish(&mut self, request_id: &u64, response: Option<&common::Output>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        let id = *request_id;
+        let response = response.cloned();
+        Box::pin(async move {
+            let response = match response {
+                Some(response) => response,
+                None => return Ok(()),
+            };
+            self.to_client.send(ToClient::Response(id, response)).await.map_err(|_| LocalError)
+        })
+    }
+
+    fn send(&mut self, _request_id: &u64, notification: &common::Notification) -> BoxFuture<'_, Result<(), Self::Error>> {
+        let notification = notification.clone();
+        Box::pin(async move {
+            self.to_client.send(ToClient::Notification(notification)).await.map_err(|_| LocalError)
+        })
+    }
+}
@@ -0,0 +1,100 @@
+//! Low-level wire protocols implemented by transports (local, HTTP, WebSocket, ...).
+//!
+//! Types in this module don't know anything about requests/responses correlation or
+//! subscriptions bookkeeping; that logic lives in [`crate::client`] and [`crate::server`], built
+//! on top of the [`RawClient`] and [`RawServer`] traits.
+
+pub mod local;
+
+use crate::common;
+use futures::future::BoxFuture;
+
+/// Something received by a [`RawClient`] while waiting for data from the server.
+#[derive(Debug)]
+pub enum RawClientEvent {
+    /// Answer to a request previously sent with [`RawClient::start_request`].
+    Response {
+        /// Identifier of the request this is an answer to.
+        id: common::Id,
+        /// Outcome of the request.
+        result: Result<common::JsonValue, common::Error>,
+    },
+    /// A notification pushed by the server outside of any request/response exchange, typically a
+    /// pub-sub subscription update.
+    SubscriptionNotif(common::JsonValue),
+}
+
+/// Low-level wire protocol used to talk to a JSON-RPC server.
+///
+/// Implementations are expected to maintain a single, possibly multiplexed, connection and to
+/// correlate incoming responses with the requests that were sent out through it.
+pub trait RawClient: Send {
+    /// Error that can be produced by this transport.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Sends a notification to the server. The server isn't expected to answer.
+    fn send_notification(&mut self, notification: common::Notification) -> BoxFuture<'_, Result<(), Self::Error>>;
+
+    /// Sends a request to the server. The answer is later reported through [`RawClient::next_event`].
+    fn start_request(&mut self, request: common::MethodCall) -> BoxFuture<'_, Result<(), Self::Error>>;
+
+    /// Sends a batch of requests to the server as a single JSON-RPC batch, per the spec. Each
+    /// answer is later reported separately through [`RawClient::next_event`], exactly as if the
+    /// calls had been sent one by one.
+    ///
+    /// The default implementation just sends every call individually; transports able to frame a
+    /// true wire-level batch (e.g. a single HTTP body or a single WebSocket message) should
+    /// override it.
+    fn start_batch(&mut self, batch: Vec<common::MethodCall>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            for call in batch {
+                self.start_request(call).await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Waits until the server sends back a response to a pending request, or an unsolicited
+    /// subscription notification.
+    fn next_event(&mut self) -> BoxFuture<'_, Result<RawClientEvent, Self::Error>>;
+}
+
+/// Something received by a [`RawServer`] from one of its clients.
+#[derive(Debug)]
+pub enum RawServerEvent<T> {
+    /// A notification, which doesn't expect any answer.
+    Notification(common::Notification),
+    /// A request that must eventually be answered through [`RawServer::finish`].
+    Request {
+        /// Opaque identifier of this request, unique among requests currently in flight.
+        id: T,
+        /// The request itself.
+        request: common::MethodCall,
+        /// Path the request arrived on, e.g. `/rpc/v0`. Always `/` for transports that don't have
+        /// a notion of path, such as the in-memory [`local`] transport.
+        path: String,
+    },
+}
+
+/// Low-level wire protocol implemented by a server transport.
+pub trait RawServer: Send {
+    /// Opaque identifier of a request, unique among the requests currently in flight.
+    type RequestId: Clone + Eq + std::hash::Hash + Send + Sync;
+    /// Error that can be produced by this transport.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Waits for a new notification or request to arrive.
+    fn next_request(&mut self) -> BoxFuture<'_, Result<RawServerEvent<Self::RequestId>, Self::Error>>;
+
+    /// Sends back the answer to a previously-received request. Passing `None` indicates that the
+    /// request was a notification and no answer should be sent on the wire.
+    fn finish(
+        &mut self,
+        request_id: &Self::RequestId,
+        response: Option<&common::Output>,
+    ) -> BoxFuture<'_, Result<(), Self::Error>>;
+
+    /// Pushes a subscription notification to whichever client originated `request_id`, outside of
+    /// any request/response exchange.
+    fn send(&mut self, request_id: &Self::RequestId, notification: &common::Notification) -> BoxFuture<'_, Result<(), Self::Error>>;
+}
@@ -0,0 +1,156 @@
+//! High-level server built on top of a [`RawServer`].
+
+pub mod params;
+pub mod routing;
+
+use crate::common::{self, JsonValue};
+use crate::raw::{RawServer, RawServerEvent};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+pub use params::Params;
+pub use routing::Router;
+
+/// Wraps around a [`RawServer`] and turns the raw notification/request events it produces into a
+/// simpler API, including bookkeeping of which in-flight requests are subscriptions.
+pub struct Server<R, I> {
+    raw: R,
+    /// Requests that have been handed out as [`Request`]s and turned into subscriptions via
+    /// [`Request::into_subscription`], and for which no [`Server::push`] has failed since.
+    subscriptions: HashSet<I>,
+    _marker: PhantomData<I>,
+}
+
+/// Event generated by [`Server::next_event`].
+pub enum ServerEvent<'a, R: RawServer<RequestId = I>, I> {
+    /// A notification, which doesn't expect any answer.
+    Notification(common::Notification),
+    /// A request that must be answered by calling [`Request::respond`].
+    Request(Request<'a, R, I>),
+    /// A subscription that had previously been turned into a subscription via
+    /// [`Request::into_subscription`] is no longer reachable; for example the underlying
+    /// connection was closed.
+    ///
+    /// [`Server::next_event`] never generates this variant: a subscription whose connection is
+    /// gone shows up as an error returned by [`Server::push`].
+    SubscriptionsClosed(I),
+}
+
+/// A request that hasn't been answered yet. Dropping this without calling [`Request::respond`] or
+/// [`Request::into_subscription`] leaves the client hanging forever.
+pub struct Request<'a, R: RawServer<RequestId = I>, I> {
+    server: &'a mut Server<R, I>,
+    id: I,
+    /// Id the client attached to this call on the wire; echoed back verbatim by [`Request::respond`].
+    wire_id: common::Id,
+    /// Path the request arrived on, e.g. `/rpc/v0`.
+    path: String,
+    method: String,
+    params: common::Params,
+}
+
+impl<R, I> Server<R, I>
+where
+    R: RawServer<RequestId = I>,
+    I: Clone + Eq + std::hash::Hash + Send + Sync,
+{
+    /// Creates a new `Server` on top of a raw transport.
+    pub fn new(raw: R) -> Self {
+        Server { raw, subscriptions: HashSet::new(), _marker: PhantomData }
+    }
+
+    /// Waits for the next notification or request to arrive.
+    pub async fn next_event(&mut self) -> Result<ServerEvent<'_, R, I>, R::Error> {
+        match self.raw.next_request().await? {
+            RawServerEvent::Notification(notif) => Ok(ServerEvent::Notification(notif)),
+            RawServerEvent::Request { id, request, path } => Ok(ServerEvent::Request(Request {
+                server: self,
+                wire_id: request.id,
+                path,
+                method: request.method,
+                params: request.params,
+                id,
+            })),
+        }
+    }
+
+    /// Looks up a request previously obtained through [`Server::next_event`] by its
+    /// [`Request::id`].
+    ///
+    /// Always returns `None`: the server keeps no table of the requests it has handed out, so a
+    /// request that is still in flight can't be retrieved again by id.
+    pub fn request_by_id(&mut self, _id: &I) -> Option<Request<'_, R, I>> {
+        // `next_event` hands each request out by value and this crate doesn't keep a side-table
+        // of not-yet-answered requests, so there is nothing to look up here.
+        None
+    }
+}
+
+impl<'a, R, I> Request<'a, R, I>
+where
+    R: RawServer<RequestId = I>,
+    I: Clone + Eq + std::hash::Hash + Send + Sync,
+{
+    /// Identifier of this request, stable for the lifetime of the underlying connection.
+    pub fn id(&self) -> &I {
+        &self.id
+    }
+
+    /// Name of the method being called.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Path the request arrived on, e.g. `/rpc/v0`. Useful together with a [`Router`] to dispatch
+    /// to the right API surface when a single server mounts several of them.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Parameters passed alongside the request.
+    pub fn params(&self) -> Params<'_> {
+        Params::from(&self.params)
+    }
+
+    /// Answers the request.
+    pub async fn respond(self, response: Result<JsonValue, common::Error>) -> Result<(), R::Error> {
+        let output = match response {
+            Ok(result) => common::Output::Success { jsonrpc: common::Version::V2, result, id: self.wire_id.clone() },
+            Err(error) => common::Output::Failure { jsonrpc: common::Version::V2, error, id: self.wire_id.clone() },
+        };
+        self.server.raw.finish(&self.id, Some(&output)).await
+    }
+
+    /// Turns this request into a long-lived subscription: no response is ever sent back on the
+    /// wire for the request itself, and notifications for the subscription are pushed through
+    /// [`Server::push`]. A client awaiting a response to the request, such as
+    /// [`Client::subscribe`](crate::client::Client::subscribe), therefore never receives one.
+    /// The request's id is tracked until a [`Server::push`] to it fails; no
+    /// [`ServerEvent::SubscriptionsClosed`] is generated for it.
+    pub fn into_subscription(self) -> I {
+        self.server.subscriptions.insert(self.id.clone());
+        self.id
+    }
+}
+
+impl<R, I> Server<R, I>
+where
+    R: RawServer<RequestId = I>,
+    I: Clone + Eq + std::hash::Hash + Send + Sync,
+{
+    /// Pushes a subscription notification to the client that owns `id`, as previously returned by
+    /// [`Request::into_subscription`].
+    ///
+    /// If the underlying transport reports that the connection is gone, `id` is dropped from the
+    /// set of tracked subscriptions and the transport's error is returned; no
+    /// [`ServerEvent::SubscriptionsClosed`] is generated for it.
+    pub async fn push(&mut self, id: &I, notification: common::Notification) -> Result<(), R::Error> {
+        match self.raw.send(id, &notification).await {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                self.subscriptions.remove(id);
+                Err(err)
+            }
+        }
+    }
+}
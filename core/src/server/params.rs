@@ -9,6 +9,7 @@ pub struct Params<'a> {
 }
 
 /// Key referring to a potential parameter of a request.
+#[derive(Clone, Copy)]
 pub enum ParamKey<'a> {
     /// String key. Only valid when the parameters list is a map.
     String(&'a str),
@@ -24,13 +25,23 @@ impl<'a> Params<'a> {
 
     /// Returns a parameter of the request by name and decodes it.
     ///
-    /// Returns an error if the parameter doesn't exist or is of the wrong type.
-    pub fn get<'k, T>(self, param: impl Into<ParamKey<'k>>) -> Result<T, ()>
+    /// Returns [`common::Error::invalid_params`] (with the parameter's key attached as `data`) if
+    /// the parameter doesn't exist or doesn't decode into `T`, so a handler can propagate the
+    /// exact code/message/data back to the caller with `rq.respond(Err(e))`.
+    pub fn get<'k, T>(self, param: impl Into<ParamKey<'k>>) -> Result<T, common::Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let val = self.get_raw(param).ok_or(())?;
-        serde_json::from_value(val.clone()).map_err(|_| ())
+        let key = param.into();
+        let val = self.get_raw(key).ok_or_else(|| {
+            common::Error::invalid_params().with_data(serde_json::json!({ "missing": format!("{:?}", key) }))
+        })?;
+        serde_json::from_value(val.clone()).map_err(|err| {
+            common::Error::invalid_params().with_data(serde_json::json!({
+                "key": format!("{:?}", key),
+                "reason": err.to_string(),
+            }))
+        })
     }
 
     /// Returns a parameter of the request by name.
@@ -58,7 +69,7 @@ impl<'a> IntoIterator for Params<'a> {
     fn into_iter(self) -> Self::IntoIter {
         Iter(match self.params {
             common::Params::None => IterInner::Empty,
-            common::Params::Array(_) => unimplemented!(),
+            common::Params::Array(array) => IterInner::Array(array.iter().enumerate()),
             common::Params::Map(map) => IterInner::Map(map.iter()),
         })
     }
@@ -66,7 +77,10 @@ impl<'a> IntoIterator for Params<'a> {
 
 impl<'a> fmt::Debug for Params<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_map().entries(self.into_iter()).finish()
+        match self.params {
+            common::Params::Array(array) => f.debug_list().entries(array.iter()).finish(),
+            common::Params::None | common::Params::Map(_) => f.debug_map().entries(self.into_iter()).finish(),
+        }
     }
 }
 
@@ -115,6 +129,7 @@ pub struct Iter<'a>(IterInner<'a>);
 enum IterInner<'a> {
     Empty,
     Map(serde_json::map::Iter<'a>),
+    Array(std::iter::Enumerate<std::slice::Iter<'a, common::JsonValue>>),
 }
 
 impl<'a> Iterator for Iter<'a> {
@@ -124,6 +139,7 @@ impl<'a> Iterator for Iter<'a> {
         match &mut self.0 {
             IterInner::Empty => None,
             IterInner::Map(iter) => iter.next().map(|(k, v)| (ParamKey::String(&k[..]), v)),
+            IterInner::Array(iter) => iter.next().map(|(i, v)| (ParamKey::Index(i), v)),
         }
     }
 
@@ -131,6 +147,7 @@ impl<'a> Iterator for Iter<'a> {
         match &self.0 {
             IterInner::Empty => (0, Some(0)),
             IterInner::Map(iter) => iter.size_hint(),
+            IterInner::Array(iter) => iter.size_hint(),
         }
     }
 }
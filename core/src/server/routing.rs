@@ -0,0 +1,48 @@
+//! Path-prefix based routing for mounting several API surfaces behind a single server.
+
+/// Maps path prefixes to registered API surfaces, so that a dispatcher can pick the right handler
+/// for a [`Request`](crate::server::Request) based on [`Request::path`](crate::server::Request::path).
+///
+/// `T` is left up to the caller: it's typically an enum of the API versions being served, or a
+/// boxed handler, whichever shape the dispatcher that sits on top needs.
+///
+/// ```
+/// # use jsonrpsee_core::server::Router;
+/// enum Api { V0, V1 }
+///
+/// let router = Router::new().register("/rpc/v0", Api::V0).register("/rpc/v1", Api::V1);
+///
+/// assert!(matches!(router.resolve("/rpc/v1"), Some(Api::V1)));
+/// assert!(router.resolve("/unknown").is_none());
+/// ```
+pub struct Router<T> {
+    routes: Vec<(String, T)>,
+}
+
+impl<T> Router<T> {
+    /// Builds an empty router; nothing matches until entries are [`register`](Router::register)ed.
+    pub fn new() -> Self {
+        Router { routes: Vec::new() }
+    }
+
+    /// Registers `api` under `path_prefix`. A request's path is routed to `api` if `path_prefix`
+    /// is a prefix of it, e.g. `/rpc/v0` matches both `/rpc/v0` and `/rpc/v0/extra`.
+    pub fn register(mut self, path_prefix: impl Into<String>, api: T) -> Self {
+        self.routes.push((path_prefix.into(), api));
+        self
+    }
+
+    /// Returns the API registered for `path`, or `None` if no registered prefix matches.
+    ///
+    /// When several registered prefixes match, the longest one wins, so that a more specific
+    /// route (e.g. `/rpc/v0/admin`) takes precedence over a broader one (e.g. `/rpc/v0`).
+    pub fn resolve(&self, path: &str) -> Option<&T> {
+        self.routes.iter().filter(|(prefix, _)| path.starts_with(prefix.as_str())).max_by_key(|(prefix, _)| prefix.len()).map(|(_, api)| api)
+    }
+}
+
+impl<T> Default for Router<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
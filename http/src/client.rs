@@ -0,0 +1,100 @@
+use jsonrpsee_core::common;
+use jsonrpsee_core::raw::{RawClient, RawClientEvent};
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+
+/// Implementation of [`RawClient`] over a plain HTTP connection.
+///
+/// Because HTTP is a pure request/response protocol, each outgoing request immediately blocks on
+/// its own answer; [`RawClient::next_event`] therefore only ever reports [`RawClientEvent::Response`]
+/// and never a subscription notification.
+pub struct HttpTransportClient {
+    /// Target URL of the server.
+    url: String,
+    /// Responses received by [`RawClient::start_request`] or [`RawClient::start_batch`], waiting
+    /// to be picked up one at a time by [`RawClient::next_event`].
+    pending: VecDeque<(common::Id, Result<common::JsonValue, common::Error>)>,
+}
+
+impl HttpTransportClient {
+    /// Creates a new client targeting `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        HttpTransportClient { url: url.into(), pending: VecDeque::new() }
+    }
+}
+
+impl RawClient for HttpTransportClient {
+    type Error = HttpTransportError;
+
+    fn send_notification(&mut self, notification: common::Notification) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let _: surf::Response = surf::post(&self.url)
+                .body(surf::http::Body::from_json(&notification).map_err(HttpTransportError::Serialize)?)
+                .await
+                .map_err(HttpTransportError::Http)?;
+            Ok(())
+        })
+    }
+
+    fn start_request(&mut self, request: common::MethodCall) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let mut response = surf::post(&self.url)
+                .body(surf::http::Body::from_json(&request).map_err(HttpTransportError::Serialize)?)
+                .await
+                .map_err(HttpTransportError::Http)?;
+            let output: common::Output = response.body_json().await.map_err(HttpTransportError::Http)?;
+            self.pending.push_back(output_to_pending(output));
+            Ok(())
+        })
+    }
+
+    /// POSTs `batch` as a single JSON array, per the spec, and buffers every answer to be handed
+    /// out one by one through [`RawClient::next_event`].
+    fn start_batch(&mut self, batch: Vec<common::MethodCall>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let request = common::Request::Batch(batch.into_iter().map(common::Call::MethodCall).collect());
+            let mut response = surf::post(&self.url)
+                .body(surf::http::Body::from_json(&request).map_err(HttpTransportError::Serialize)?)
+                .await
+                .map_err(HttpTransportError::Http)?;
+            let response: common::Response = response.body_json().await.map_err(HttpTransportError::Http)?;
+            match response {
+                common::Response::Single(output) => self.pending.push_back(output_to_pending(output)),
+                common::Response::Batch(outputs) => {
+                    self.pending.extend(outputs.into_iter().map(output_to_pending))
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn next_event(&mut self) -> BoxFuture<'_, Result<RawClientEvent, Self::Error>> {
+        Box::pin(async move {
+            let (id, result) = self.pending.pop_front().ok_or(HttpTransportError::NoPendingResponse)?;
+            Ok(RawClientEvent::Response { id, result })
+        })
+    }
+}
+
+fn output_to_pending(output: common::Output) -> (common::Id, Result<common::JsonValue, common::Error>) {
+    let id = output.id().clone();
+    match output {
+        common::Output::Success { result, .. } => (id, Ok(result)),
+        common::Output::Failure { error, .. } => (id, Err(error)),
+    }
+}
+
+/// Error that can happen when using [`HttpTransportClient`].
+#[derive(Debug, thiserror::Error)]
+pub enum HttpTransportError {
+    /// Error returned by the HTTP layer itself.
+    #[error("HTTP error: {0}")]
+    Http(surf::Error),
+    /// Failed to serialize the outgoing request as JSON.
+    #[error("failed to serialize request: {0}")]
+    Serialize(surf::Error),
+    /// [`RawClient::next_event`] was called without a prior call to
+    /// [`RawClient::start_request`] having completed.
+    #[error("no response is pending")]
+    NoPendingResponse,
+}
@@ -0,0 +1,28 @@
+//! HTTP transport for `jsonrpsee`.
+//!
+//! Every client request opens (or reuses, via keep-alive) a short-lived HTTP connection carrying
+//! a single JSON-RPC request/response pair. Because HTTP has no way for a server to push data to
+//! a client outside of answering a request, this transport cannot deliver pub-sub notifications;
+//! see `jsonrpsee-ws` for that.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+mod client;
+mod server;
+
+pub use client::HttpTransportClient;
+pub use server::HttpTransportServer;
+
+use jsonrpsee_core::{Client, Server};
+use std::net::SocketAddr;
+
+/// Creates a new HTTP client connecting to `url`.
+pub fn http_client(url: &str) -> Client<HttpTransportClient> {
+    Client::new(HttpTransportClient::new(url))
+}
+
+/// Creates a new HTTP server listening on `addr`.
+pub async fn http_server(addr: &SocketAddr) -> Result<Server<HttpTransportServer, <HttpTransportServer as jsonrpsee_core::RawServer>::RequestId>, std::io::Error> {
+    Ok(Server::new(HttpTransportServer::bind(addr).await?))
+}
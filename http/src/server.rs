@@ -0,0 +1,189 @@
+use jsonrpsee_core::common;
+use jsonrpsee_core::raw::{RawServer, RawServerEvent};
+use jsonrpsee_core::batch::BatchState;
+use async_std::sync::Mutex;
+use futures::{channel::{mpsc, oneshot}, future::BoxFuture, prelude::*};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+/// Implementation of [`RawServer`] over a plain HTTP connection.
+///
+/// A single HTTP server listens for incoming connections in the background; each incoming request
+/// is forwarded to [`RawServer::next_request`] and the call doesn't return to the HTTP layer
+/// until [`RawServer::finish`] has supplied an answer, since HTTP cannot push data outside of a
+/// response. A JSON-RPC batch is POSTed as a single HTTP request and is likewise answered with a
+/// single HTTP response, only sent back once every method call of the batch has been answered.
+/// Requests are accepted on any path, which is reported alongside each request so that a
+/// [`Router`](jsonrpsee_core::server::Router) built on top can dispatch between several API
+/// surfaces mounted on the same listening socket.
+pub struct HttpTransportServer {
+    from_requests: mpsc::UnboundedReceiver<RawServerEvent<u64>>,
+    pending_responses: Arc<Mutex<HashMap<u64, PendingSlot>>>,
+}
+
+/// What a given request id's answer must be routed to once [`RawServer::finish`] supplies it.
+enum PendingSlot {
+    /// Answer directly a single HTTP request.
+    Single(oneshot::Sender<common::Response>),
+    /// Fill slot `index` of a batch; the combined response is sent once every slot is filled.
+    Batch { shared: Arc<SharedBatch>, index: usize },
+}
+
+struct SharedBatch {
+    state: BatchState<common::Output>,
+    /// Taken by whichever `finish` call completes the last slot.
+    final_tx: Mutex<Option<oneshot::Sender<common::Response>>>,
+}
+
+type SharedState = (mpsc::UnboundedSender<RawServerEvent<u64>>, Arc<Mutex<HashMap<u64, PendingSlot>>>, Arc<Mutex<u64>>);
+
+impl HttpTransportServer {
+    /// Binds to `addr` and starts accepting connections in the background.
+    pub async fn bind(addr: &SocketAddr) -> Result<Self, std::io::Error> {
+        let (to_requests, from_requests) = mpsc::unbounded();
+        let pending_responses = Arc::new(Mutex::new(HashMap::new()));
+        let next_id = Arc::new(Mutex::new(0u64));
+
+        let mut app = tide::with_state((to_requests, pending_responses.clone(), next_id));
+        app.at("/").post(handle_tide_request);
+        app.at("/*path").post(handle_tide_request);
+
+        let listener = addr.to_string();
+        async_std::task::spawn(async move {
+            let _ = app.listen(listener).await;
+        });
+
+        Ok(HttpTransportServer { from_requests, pending_responses })
+    }
+}
+
+async fn alloc_id(next_id: &Arc<Mutex<u64>>) -> u64 {
+    let mut next_id = next_id.lock().await;
+    let id = *next_id;
+    *next_id += 1;
+    id
+}
+
+async fn handle_tide_request(mut req: tide::Request<SharedState>) -> tide::Result<tide::Response> {
+    let path = req.url().path().to_owned();
+    let request: common::Request = req.body_json().await?;
+    let (to_requests, pending_responses, next_id) = req.state().clone();
+    let response = handle_request(request, &path, &to_requests, &pending_responses, &next_id).await?;
+    match response {
+        Some(response) => Ok(tide::Response::builder(200).body(tide::Body::from_json(&response)?).build()),
+        None => Ok(tide::Response::builder(204).build()),
+    }
+}
+
+async fn handle_request(
+    request: common::Request,
+    path: &str,
+    to_requests: &mpsc::UnboundedSender<RawServerEvent<u64>>,
+    pending_responses: &Arc<Mutex<HashMap<u64, PendingSlot>>>,
+    next_id: &Arc<Mutex<u64>>,
+) -> tide::Result<Option<common::Response>> {
+    let is_batch = matches!(request, common::Request::Batch(_));
+    let calls = match request {
+        common::Request::Single(call) => vec![call],
+        common::Request::Batch(calls) => calls,
+    };
+
+    // Per the spec, a batch made of nothing or of only notifications gets no response at all.
+    let num_method_calls = calls.iter().filter(|call| matches!(call, common::Call::MethodCall(_))).count();
+    if num_method_calls == 0 {
+        for call in calls {
+            if let common::Call::Notification(notif) = call {
+                let _ = to_requests.unbounded_send(RawServerEvent::Notification(notif));
+            }
+        }
+        return Ok(None);
+    }
+
+    // A lone method call sent outside of a batch is answered directly, without going through the
+    // batch-assembly machinery (a single-element batch must still come back as a one-element
+    // array, so it still takes the path below).
+    if !is_batch {
+        if let common::Call::MethodCall(call) = calls.into_iter().next().expect("num_method_calls == 1 implies one call") {
+            let id = alloc_id(next_id).await;
+            let (tx, rx) = oneshot::channel();
+            pending_responses.lock().await.insert(id, PendingSlot::Single(tx));
+            to_requests
+                .unbounded_send(RawServerEvent::Request { id, request: call, path: path.to_owned() })
+                .map_err(|_| tide::Error::from_str(500, "server shut down"))?;
+            let response = rx.await.map_err(|_| tide::Error::from_str(500, "request dropped without an answer"))?;
+            return Ok(Some(response));
+        }
+        unreachable!("num_method_calls == 1 implies the single call is a MethodCall");
+    }
+
+    let (final_tx, final_rx) = oneshot::channel();
+    let shared = Arc::new(SharedBatch { state: BatchState::new(num_method_calls), final_tx: Mutex::new(Some(final_tx)) });
+
+    let mut index = 0;
+    for call in calls {
+        match call {
+            common::Call::Notification(notif) => {
+                let _ = to_requests.unbounded_send(RawServerEvent::Notification(notif));
+            }
+            common::Call::MethodCall(call) => {
+                let id = alloc_id(next_id).await;
+                pending_responses.lock().await.insert(id, PendingSlot::Batch { shared: shared.clone(), index });
+                to_requests
+                    .unbounded_send(RawServerEvent::Request { id, request: call, path: path.to_owned() })
+                    .map_err(|_| tide::Error::from_str(500, "server shut down"))?;
+                index += 1;
+            }
+        }
+    }
+
+    let response = final_rx.await.map_err(|_| tide::Error::from_str(500, "batch dropped without an answer"))?;
+    Ok(Some(response))
+}
+
+impl RawServer for HttpTransportServer {
+    type RequestId = u64;
+    type Error = std::io::Error;
+
+    fn next_request(&mut self) -> BoxFuture<'_, Result<RawServerEvent<u64>, Self::Error>> {
+        Box::pin(async move {
+            self.from_requests
+                .next()
+                .await
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "HTTP server shut down"))
+        })
+    }
+
+    fn finish(&mut self, request_id: &u64, response: Option<&common::Output>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        let id = *request_id;
+        let response = response.cloned();
+        let pending_responses = self.pending_responses.clone();
+        Box::pin(async move {
+            let output = match response {
+                Some(output) => output,
+                // An HTTP method call always expects an answer; a bare notification never gets a
+                // pending slot in the first place.
+                None => return Ok(()),
+            };
+            let slot = pending_responses.lock().await.remove(&id);
+            match slot {
+                Some(PendingSlot::Single(tx)) => {
+                    let _ = tx.send(common::Response::Single(output));
+                }
+                Some(PendingSlot::Batch { shared, index }) => {
+                    if let Some(outputs) = shared.state.fill(index, output) {
+                        if let Some(tx) = shared.final_tx.lock().await.take() {
+                            let _ = tx.send(common::Response::Batch(outputs));
+                        }
+                    }
+                }
+                None => {}
+            }
+            Ok(())
+        })
+    }
+
+    fn send(&mut self, _request_id: &u64, _notification: &common::Notification) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "the HTTP transport cannot push unsolicited notifications"))
+        })
+    }
+}
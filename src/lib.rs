@@ -115,6 +115,24 @@
 //! # }
 //! ```
 //!
+//! # Pub-sub over WebSockets
+//!
+//! HTTP has no way for a server to push data outside of answering a request, so pub-sub
+//! notifications (such as `eth_subscribe` updates) require a transport that keeps a connection
+//! open. [`ws_client`] and [`ws_server`] are the WebSocket counterparts of [`http_client`] and
+//! [`http_server`] that do exactly that (unlike [`http_client`], [`ws_client`] is `async` and
+//! returns a `Result`, since it connects up front): the same [`Client::request`](core::client::Client::request)
+//! and [`Client::subscribe`](core::client::Client::subscribe) calls work unchanged, and
+//! notifications pushed by the server surface through the returned subscription.
+//!
+//! # Mounting several APIs on one server
+//!
+//! [`http_server`] and [`ws_server`] accept requests on any path, and
+//! [`Request::path`](core::server::Request::path) reports which one a given request came in on.
+//! [`core::server::Router`] turns that into a dispatch table: register an API surface against
+//! each path prefix (e.g. `/rpc/v0`, `/rpc/v1`), then call [`Router::resolve`](core::server::Router::resolve)
+//! with the incoming request's path to pick the matching handler.
+//!
 
 #![deny(unsafe_code)]
 #![deny(intra_doc_link_resolution_failure)]
@@ -122,6 +140,8 @@
 
 #[cfg(feature = "http")]
 pub use jsonrpsee_http::{http_client, http_server};
+#[cfg(feature = "ws")]
+pub use jsonrpsee_ws::{ws_client, ws_server};
 pub use jsonrpsee_proc_macros::rpc_api;
 
 #[doc(inline)]
@@ -129,6 +149,9 @@ pub use jsonrpsee_core as core;
 #[doc(inline)]
 #[cfg(feature = "http")]
 pub use jsonrpsee_http as http;
+#[doc(inline)]
+#[cfg(feature = "ws")]
+pub use jsonrpsee_ws as ws;
 
 /// Builds a new client and a new server that are connected to each other.
 pub fn local() -> (core::Client<core::local::LocalRawClient>, core::Server<core::local::LocalRawServer, <core::local::LocalRawServer as core::RawServer>::RequestId>) {
@@ -0,0 +1,110 @@
+use jsonrpsee_core::common;
+use jsonrpsee_core::raw::{RawClient, RawClientEvent};
+use futures::future::BoxFuture;
+use soketto::handshake::{Client as HandshakeClient, ServerResponse};
+
+/// Implementation of [`RawClient`] over a single, persistent WebSocket connection.
+pub struct WsTransportClient {
+    sender: soketto::connection::Sender<async_std::net::TcpStream>,
+    receiver: soketto::connection::Receiver<async_std::net::TcpStream>,
+}
+
+impl WsTransportClient {
+    /// Connects to `url` and performs the WebSocket handshake.
+    pub async fn new(url: &str) -> Result<Self, WsConnecError> {
+        let parsed = url::Url::parse(url).map_err(|_| WsConnecError::InvalidUrl)?;
+        if parsed.scheme() != "ws" && parsed.scheme() != "wss" {
+            return Err(WsConnecError::InvalidUrl);
+        }
+        let host = parsed.host_str().ok_or(WsConnecError::InvalidUrl)?;
+        let port = parsed.port_or_known_default().unwrap_or(80);
+        let tcp_stream = async_std::net::TcpStream::connect((host, port)).await.map_err(WsConnecError::Io)?;
+
+        let mut client = HandshakeClient::new(tcp_stream, host, parsed.path());
+        match client.handshake().await.map_err(WsConnecError::Handshake)? {
+            ServerResponse::Accepted { .. } => {}
+            ServerResponse::Rejected { .. } | ServerResponse::Redirect { .. } => {
+                return Err(WsConnecError::Rejected);
+            }
+        }
+
+        let (sender, receiver) = client.into_builder().finish();
+        Ok(WsTransportClient { sender, receiver })
+    }
+}
+
+impl RawClient for WsTransportClient {
+    type Error = WsError;
+
+    fn send_notification(&mut self, notification: common::Notification) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let data = serde_json::to_string(&notification).map_err(WsError::Serialize)?;
+            self.sender.send_text(data).await.map_err(WsError::Connection)?;
+            self.sender.flush().await.map_err(WsError::Connection)
+        })
+    }
+
+    fn start_request(&mut self, request: common::MethodCall) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let data = serde_json::to_string(&request).map_err(WsError::Serialize)?;
+            self.sender.send_text(data).await.map_err(WsError::Connection)?;
+            self.sender.flush().await.map_err(WsError::Connection)
+        })
+    }
+
+    fn next_event(&mut self) -> BoxFuture<'_, Result<RawClientEvent, Self::Error>> {
+        Box::pin(async move {
+            let mut data = Vec::new();
+            self.receiver.receive_data(&mut data).await.map_err(WsError::Connection)?;
+
+            let value: common::JsonValue = serde_json::from_slice(&data).map_err(WsError::Deserialize)?;
+            // A message carrying an "id" is the answer to one of our requests; anything else
+            // (an unsolicited notification, e.g. a pub-sub update) is routed to the subscription
+            // channel instead.
+            if value.get("id").is_some() {
+                let output: common::Output = serde_json::from_value(value).map_err(WsError::Deserialize)?;
+                let id = output.id().clone();
+                let result = match output {
+                    common::Output::Success { result, .. } => Ok(result),
+                    common::Output::Failure { error, .. } => Err(error),
+                };
+                Ok(RawClientEvent::Response { id, result })
+            } else {
+                let notif: common::Notification = serde_json::from_value(value).map_err(WsError::Deserialize)?;
+                let params = serde_json::to_value(&notif.params).unwrap_or(common::JsonValue::Null);
+                Ok(RawClientEvent::SubscriptionNotif(params))
+            }
+        })
+    }
+}
+
+/// Error that can happen while establishing a [`WsTransportClient`].
+#[derive(Debug, thiserror::Error)]
+pub enum WsConnecError {
+    /// The given URL couldn't be parsed or didn't have a `ws://`/`wss://` scheme.
+    #[error("invalid WebSocket URL")]
+    InvalidUrl,
+    /// Failed to open the underlying TCP connection.
+    #[error("I/O error: {0}")]
+    Io(std::io::Error),
+    /// The WebSocket handshake itself failed.
+    #[error("WebSocket handshake failed: {0}")]
+    Handshake(soketto::handshake::Error),
+    /// The server rejected or redirected the handshake.
+    #[error("WebSocket handshake was rejected by the server")]
+    Rejected,
+}
+
+/// Error that can happen once a [`WsTransportClient`] is connected.
+#[derive(Debug, thiserror::Error)]
+pub enum WsError {
+    /// Error at the WebSocket connection level.
+    #[error("WebSocket connection error: {0}")]
+    Connection(soketto::connection::Error),
+    /// Failed to serialize an outgoing message as JSON.
+    #[error("failed to serialize message: {0}")]
+    Serialize(serde_json::Error),
+    /// Failed to deserialize an incoming message as JSON.
+    #[error("failed to deserialize message: {0}")]
+    Deserialize(serde_json::Error),
+}
@@ -0,0 +1,29 @@
+//! WebSocket transport for `jsonrpsee`.
+//!
+//! Unlike the HTTP transport, a WebSocket connection stays open for as long as the client wants
+//! it to, so it is the transport to reach for whenever server-pushed notifications are involved,
+//! such as Ethereum-style `eth_subscribe` pub-sub. A single connection is multiplexed: several
+//! requests can be in flight at once, and incoming messages without a pending-request id are
+//! routed to the subscription notification channel instead of being matched against a request.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+mod client;
+mod server;
+
+pub use client::WsTransportClient;
+pub use server::WsTransportServer;
+
+use jsonrpsee_core::{Client, Server};
+use std::net::SocketAddr;
+
+/// Creates a new WebSocket client connecting to `url` (e.g. `"ws://127.0.0.1:8546"`).
+pub async fn ws_client(url: &str) -> Result<Client<WsTransportClient>, client::WsConnecError> {
+    Ok(Client::new(WsTransportClient::new(url).await?))
+}
+
+/// Creates a new WebSocket server listening on `addr`.
+pub async fn ws_server(addr: &SocketAddr) -> Result<Server<WsTransportServer, <WsTransportServer as jsonrpsee_core::RawServer>::RequestId>, std::io::Error> {
+    Ok(Server::new(WsTransportServer::bind(addr).await?))
+}
@@ -0,0 +1,223 @@
+use jsonrpsee_core::common;
+use jsonrpsee_core::raw::{RawServer, RawServerEvent};
+use jsonrpsee_core::batch::BatchState;
+use async_std::{net::TcpListener, sync::Mutex};
+use futures::{channel::mpsc, future::BoxFuture, prelude::*};
+use soketto::handshake::{server::Response, Server as HandshakeServer};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+/// Identifier of a request received over a [`WsTransportServer`]: the connection it came in on,
+/// plus the JSON-RPC id the client attached to it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WsRequestId {
+    connection: u64,
+    id: common::Id,
+}
+
+type Outgoing = mpsc::UnboundedSender<String>;
+
+/// What a given [`WsRequestId`]'s answer must be routed to.
+enum PendingSlot {
+    /// Send the answer to the connection as soon as it comes in.
+    Single,
+    /// Fill slot `index` of a batch; the combined response is sent once every slot is filled.
+    Batch { shared: Arc<SharedBatch>, index: usize },
+}
+
+struct SharedBatch {
+    state: BatchState<common::Output>,
+}
+
+/// Implementation of [`RawServer`] over one or more persistent WebSocket connections.
+///
+/// Every accepted connection is driven by its own background task; incoming requests and
+/// notifications are funneled into a single queue drained by [`RawServer::next_request`], and
+/// answers (or subscription pushes) are routed back to the connection they belong to. A JSON-RPC
+/// batch received in a single WebSocket message is answered with a single combined message, once
+/// every method call of the batch has been answered. The path requested during the handshake is
+/// remembered for the lifetime of the connection and reported alongside every request coming from
+/// it, so that a [`Router`](jsonrpsee_core::server::Router) built on top can dispatch between
+/// several API surfaces mounted on the same listening socket.
+pub struct WsTransportServer {
+    from_connections: mpsc::UnboundedReceiver<(u64, RawServerEvent<common::Id>)>,
+    connections: Arc<Mutex<HashMap<u64, Outgoing>>>,
+    pending: Arc<Mutex<HashMap<(u64, common::Id), PendingSlot>>>,
+}
+
+impl WsTransportServer {
+    /// Binds to `addr` and starts accepting connections in the background.
+    pub async fn bind(addr: &SocketAddr) -> Result<Self, std::io::Error> {
+        let listener = TcpListener::bind(addr).await?;
+        let connections: Arc<Mutex<HashMap<u64, Outgoing>>> = Arc::new(Mutex::new(HashMap::new()));
+        let pending: Arc<Mutex<HashMap<(u64, common::Id), PendingSlot>>> = Arc::new(Mutex::new(HashMap::new()));
+        let (to_server, from_connections) = mpsc::unbounded();
+
+        let connections_clone = connections.clone();
+        let pending_clone = pending.clone();
+        async_std::task::spawn(async move {
+            let mut next_connection_id = 0u64;
+            let mut incoming = listener.incoming();
+            while let Some(Ok(stream)) = incoming.next().await {
+                let connection_id = next_connection_id;
+                next_connection_id += 1;
+
+                let to_server = to_server.clone();
+                let connections = connections_clone.clone();
+                let pending = pending_clone.clone();
+                async_std::task::spawn(async move {
+                    let _ = handle_connection(stream, connection_id, to_server, connections, pending).await;
+                });
+            }
+        });
+
+        Ok(WsTransportServer { from_connections, connections, pending })
+    }
+}
+
+async fn handle_connection(
+    stream: async_std::net::TcpStream,
+    connection_id: u64,
+    to_server: mpsc::UnboundedSender<(u64, RawServerEvent<common::Id>)>,
+    connections: Arc<Mutex<HashMap<u64, Outgoing>>>,
+    pending: Arc<Mutex<HashMap<(u64, common::Id), PendingSlot>>>,
+) -> Result<(), soketto::handshake::Error> {
+    let mut server = HandshakeServer::new(stream);
+    let (key, path) = {
+        let req = server.receive_request().await?;
+        (req.key(), req.path().to_owned())
+    };
+    server.send_response(&Response::Accept { key, protocol: None }).await?;
+    let (mut sender, mut receiver) = server.into_builder().finish();
+
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded();
+    connections.lock().await.insert(connection_id, outgoing);
+
+    async_std::task::spawn(async move {
+        while let Some(message) = outgoing_rx.next().await {
+            if sender.send_text(message).await.is_err() || sender.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let mut data = Vec::new();
+        if receiver.receive_data(&mut data).await.is_err() {
+            break;
+        }
+        let request: common::Request = match serde_json::from_slice(&data) {
+            Ok(request) => request,
+            Err(_) => continue,
+        };
+        let is_batch = matches!(request, common::Request::Batch(_));
+        let calls = match request {
+            common::Request::Single(call) => vec![call],
+            common::Request::Batch(calls) => calls,
+        };
+
+        // Per the spec, a batch made of nothing or of only notifications gets no response.
+        let num_method_calls = calls.iter().filter(|call| matches!(call, common::Call::MethodCall(_))).count();
+        let shared_batch = if is_batch && num_method_calls > 0 {
+            Some(Arc::new(SharedBatch { state: BatchState::new(num_method_calls) }))
+        } else {
+            None
+        };
+
+        let mut index = 0;
+        for call in calls {
+            match call {
+                common::Call::Notification(notif) => {
+                    let _ = to_server.unbounded_send((connection_id, RawServerEvent::Notification(notif)));
+                }
+                common::Call::MethodCall(call) => {
+                    let slot = match &shared_batch {
+                        Some(shared) => {
+                            let slot = PendingSlot::Batch { shared: shared.clone(), index };
+                            index += 1;
+                            slot
+                        }
+                        None => PendingSlot::Single,
+                    };
+                    pending.lock().await.insert((connection_id, call.id.clone()), slot);
+                    let id = call.id.clone();
+                    let event = RawServerEvent::Request { id, request: call, path: path.clone() };
+                    if to_server.unbounded_send((connection_id, event)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    connections.lock().await.remove(&connection_id);
+    Ok(())
+}
+
+impl RawServer for WsTransportServer {
+    type RequestId = WsRequestId;
+    type Error = std::io::Error;
+
+    fn next_request(&mut self) -> BoxFuture<'_, Result<RawServerEvent<Self::RequestId>, Self::Error>> {
+        Box::pin(async move {
+            let (connection, event) = self
+                .from_connections
+                .next()
+                .await
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "WebSocket server shut down"))?;
+            Ok(match event {
+                RawServerEvent::Notification(notif) => RawServerEvent::Notification(notif),
+                RawServerEvent::Request { id, request, path } => {
+                    RawServerEvent::Request { id: WsRequestId { connection, id }, request, path }
+                }
+            })
+        })
+    }
+
+    fn finish(&mut self, request_id: &Self::RequestId, response: Option<&common::Output>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        let connection = request_id.connection;
+        let key = (connection, request_id.id.clone());
+        let response = response.cloned();
+        let connections = self.connections.clone();
+        let pending = self.pending.clone();
+        Box::pin(async move {
+            let output = match response {
+                Some(output) => output,
+                None => return Ok(()),
+            };
+            match pending.lock().await.remove(&key) {
+                Some(PendingSlot::Single) | None => {
+                    send_to_connection(&connections, connection, serde_json::to_string(&common::Response::Single(output))).await
+                }
+                Some(PendingSlot::Batch { shared, index }) => {
+                    if let Some(outputs) = shared.state.fill(index, output) {
+                        send_to_connection(&connections, connection, serde_json::to_string(&common::Response::Batch(outputs))).await
+                    } else {
+                        Ok(())
+                    }
+                }
+            }
+        })
+    }
+
+    fn send(&mut self, request_id: &Self::RequestId, notification: &common::Notification) -> BoxFuture<'_, Result<(), Self::Error>> {
+        let connection = request_id.connection;
+        let notification = notification.clone();
+        let connections = self.connections.clone();
+        Box::pin(async move { send_to_connection(&connections, connection, serde_json::to_string(&notification)).await })
+    }
+}
+
+async fn send_to_connection(
+    connections: &Arc<Mutex<HashMap<u64, Outgoing>>>,
+    connection: u64,
+    message: serde_json::Result<String>,
+) -> Result<(), std::io::Error> {
+    let message = message.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let connections = connections.lock().await;
+    match connections.get(&connection) {
+        Some(sender) => sender
+            .unbounded_send(message)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "connection closed")),
+        None => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "connection closed")),
+    }
+}